@@ -0,0 +1,322 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use prometheus::{
+    opts, register_int_counter_vec, register_int_gauge, register_int_gauge_vec, IntCounterVec,
+    IntGauge, IntGaugeVec,
+};
+use solana_lite_rpc_core::AnyhowJoinHandle;
+use yellowstone_grpc_client::GeyserGrpcClient;
+use yellowstone_grpc_proto::{
+    geyser::{
+        subscribe_update::UpdateOneof, CommitmentLevel as GeyserCommitmentLevel, SubscribeRequest,
+        SubscribeRequestFilterBlocks,
+    },
+    prelude::SubscribeUpdateBlock,
+};
+
+use crate::endpoint_stremers::EndpointStreaming;
+use crate::json_rpc_subscription::{fan_in_dedup_blocks, fan_in_dedup_slots, SourceHealthMetrics};
+use solana_lite_rpc_core::block_processor::{
+    compute_prioritization_fees, decode_compute_budget_instructions, is_simple_vote_transaction,
+    BlockProcessorResult, TransactionInfo,
+};
+
+lazy_static! {
+    static ref GRPC_SOURCE_LAST_SLOT_SEEN: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "literpc_grpc_source_last_slot_seen",
+            "Last slot produced by each configured gRPC source"
+        ),
+        &["source"]
+    )
+    .unwrap();
+    static ref GRPC_SOURCE_HEALTHY: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "literpc_grpc_source_healthy",
+            "Whether a gRPC source has produced a slot within its staleness window"
+        ),
+        &["source"]
+    )
+    .unwrap();
+    static ref GRPC_SOURCE_STREAM_ERRORS: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "literpc_grpc_source_stream_errors",
+            "Number of times a gRPC source's stream errored or was lagged"
+        ),
+        &["source"]
+    )
+    .unwrap();
+    /// Fed to the shared `fan_in_dedup_slots`/`fan_in_dedup_blocks` helpers so the gRPC transport
+    /// reports into its own `literpc_grpc_source_*` metrics rather than the RPC-polling ones.
+    static ref GRPC_SOURCE_HEALTH_METRICS: SourceHealthMetrics = SourceHealthMetrics {
+        healthy: &GRPC_SOURCE_HEALTHY,
+        last_slot_seen: &GRPC_SOURCE_LAST_SLOT_SEEN,
+        stream_errors: &GRPC_SOURCE_STREAM_ERRORS,
+    };
+    /// Set to 1 for as long as the gRPC transport is active, since it doesn't produce
+    /// cluster-node/vote-account info; `getClusterNodes`/`getVoteAccounts`-style consumers of
+    /// `cluster_info_notifier`/`vote_account_notifier` see those streams close immediately.
+    static ref GRPC_CLUSTER_INFO_UNAVAILABLE: IntGauge = register_int_gauge!(opts!(
+        "literpc_grpc_cluster_info_unavailable",
+        "1 while the gRPC block transport is active, since it can't supply cluster/vote-account info"
+    ))
+    .unwrap();
+    /// Set to 1 for as long as the gRPC transport is active, since `process_geyser_block` doesn't
+    /// resolve per-account writability yet and always reports no contended accounts.
+    static ref GRPC_ACCOUNT_CONTENTION_UNAVAILABLE: IntGauge = register_int_gauge!(opts!(
+        "literpc_grpc_account_contention_unavailable",
+        "1 while the gRPC block transport is active, since it can't report heavily locked accounts"
+    ))
+    .unwrap();
+}
+
+/// Ensures the account-contention gap is logged once on first use rather than once per block.
+static ACCOUNT_CONTENTION_GAP_LOGGED: std::sync::Once = std::sync::Once::new();
+
+/// One Yellowstone geyser gRPC endpoint to subscribe to.
+///
+/// Multiple sources can be configured; each streams independently into a shared fan-in stage
+/// that de-duplicates by slot/blockhash and forwards only the first copy of each, mirroring how
+/// `create_json_rpc_polling_subscription` de-duplicates across RPC polling sources.
+#[derive(Clone, Debug)]
+pub struct GrpcSourceConfig {
+    pub url: String,
+    pub x_token: Option<String>,
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    /// Used only to label Prometheus metrics for this source.
+    pub label: String,
+}
+
+impl GrpcSourceConfig {
+    pub fn new(label: String, url: String, x_token: Option<String>) -> Self {
+        Self {
+            url,
+            x_token,
+            connect_timeout: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(10),
+            label,
+        }
+    }
+}
+
+const RECONNECT_BACKOFF: Duration = Duration::from_secs(1);
+
+pub fn create_grpc_subscription(
+    grpc_sources: Vec<GrpcSourceConfig>,
+) -> anyhow::Result<(EndpointStreaming, Vec<AnyhowJoinHandle>)> {
+    if grpc_sources.is_empty() {
+        bail!("create_grpc_subscription requires at least one gRPC source");
+    }
+
+    let (slot_sx, slot_notifier) = tokio::sync::broadcast::channel(10);
+    let (block_sx, blocks_notifier) = tokio::sync::broadcast::channel(10);
+    let (cluster_info_sx, cluster_info_notifier) = tokio::sync::broadcast::channel(10);
+    let (va_sx, vote_account_notifier) = tokio::sync::broadcast::channel(10);
+    let (prioritization_fees_sx, prioritization_fees_notifier) =
+        tokio::sync::broadcast::channel(10);
+    // The gRPC transport has no cluster-node/vote-account source today, so `cluster_info_sx`
+    // and `va_sx` are dropped immediately and their notifiers close right away. That silently
+    // breaks any `getClusterNodes`/`getVoteAccounts`-style feature running on this transport, so
+    // make the gap loud instead of a comment nobody reads at 3am.
+    warn!(
+        "grpc transport has no cluster-node/vote-account source: getClusterNodes/getVoteAccounts \
+         consumers will see their stream close immediately"
+    );
+    GRPC_CLUSTER_INFO_UNAVAILABLE.set(1);
+    drop(cluster_info_sx);
+    drop(va_sx);
+
+    let mut endpoint_tasks = Vec::with_capacity(grpc_sources.len() + 2);
+    let mut per_source_slot_receivers = Vec::with_capacity(grpc_sources.len());
+    let mut per_source_block_receivers = Vec::with_capacity(grpc_sources.len());
+    let mut labels = Vec::with_capacity(grpc_sources.len());
+
+    for source in grpc_sources {
+        let (source_slot_sx, source_slot_notifier) = tokio::sync::broadcast::channel(10);
+        let (source_block_sx, source_block_notifier) = tokio::sync::broadcast::channel(10);
+        labels.push(source.label.clone());
+        let task: AnyhowJoinHandle =
+            tokio::spawn(subscribe_blocks(source, source_block_sx, source_slot_sx));
+        endpoint_tasks.push(task);
+        per_source_slot_receivers.push(source_slot_notifier);
+        per_source_block_receivers.push(source_block_notifier);
+    }
+
+    endpoint_tasks.push(fan_in_dedup_slots(
+        per_source_slot_receivers,
+        labels.clone(),
+        slot_sx,
+        &GRPC_SOURCE_HEALTH_METRICS,
+    ));
+    endpoint_tasks.push(fan_in_dedup_blocks(
+        per_source_block_receivers,
+        labels,
+        block_sx,
+        prioritization_fees_sx,
+        &GRPC_SOURCE_HEALTH_METRICS,
+    ));
+
+    let streamers = EndpointStreaming {
+        blocks_notifier,
+        slot_notifier,
+        cluster_info_notifier,
+        vote_account_notifier,
+        prioritization_fees_notifier,
+    };
+    Ok((streamers, endpoint_tasks))
+}
+
+async fn subscribe_blocks(
+    source: GrpcSourceConfig,
+    block_sx: tokio::sync::broadcast::Sender<BlockProcessorResult>,
+    slot_sx: tokio::sync::broadcast::Sender<solana_sdk::slot_history::Slot>,
+) -> anyhow::Result<()> {
+    loop {
+        if let Err(err) = run_grpc_stream(&source, &block_sx, &slot_sx).await {
+            GRPC_SOURCE_STREAM_ERRORS
+                .with_label_values(&[&source.label])
+                .inc();
+            error!(
+                "grpc block subscription to {} failed, reconnecting in {:?}: {err:?}",
+                source.url, RECONNECT_BACKOFF
+            );
+            tokio::time::sleep(RECONNECT_BACKOFF).await;
+            continue;
+        }
+        warn!(
+            "grpc block subscription to {} closed, reconnecting",
+            source.url
+        );
+        tokio::time::sleep(RECONNECT_BACKOFF).await;
+    }
+}
+
+async fn run_grpc_stream(
+    source: &GrpcSourceConfig,
+    block_sx: &tokio::sync::broadcast::Sender<BlockProcessorResult>,
+    slot_sx: &tokio::sync::broadcast::Sender<solana_sdk::slot_history::Slot>,
+) -> anyhow::Result<()> {
+    let mut client = GeyserGrpcClient::connect_with_timeout(
+        source.url.clone(),
+        source.x_token.clone(),
+        None,
+        Some(source.connect_timeout),
+        Some(source.request_timeout),
+        false,
+    )
+    .await
+    .with_context(|| format!("connecting to grpc source {}", source.url))?;
+
+    let mut blocks_filter = std::collections::HashMap::new();
+    blocks_filter.insert(
+        "lite_rpc_blocks".to_string(),
+        SubscribeRequestFilterBlocks {
+            account_include: vec![],
+            include_transactions: Some(true),
+            include_accounts: Some(false),
+            include_entries: Some(false),
+        },
+    );
+
+    let request = SubscribeRequest {
+        blocks: blocks_filter,
+        commitment: Some(GeyserCommitmentLevel::Confirmed as i32),
+        ..Default::default()
+    };
+
+    let (_subscribe_tx, mut stream) = client.subscribe_with_request(Some(request)).await?;
+
+    info!("subscribed to grpc block stream on {}", source.url);
+
+    while let Some(message) = stream.next().await {
+        let update = message.context("grpc stream error")?;
+        if let Some(UpdateOneof::Block(block)) = update.update_oneof {
+            let _ = slot_sx.send(block.slot);
+            let result = process_geyser_block(block);
+            let _ = block_sx.send(result);
+        }
+    }
+
+    Ok(())
+}
+
+fn process_geyser_block(block: SubscribeUpdateBlock) -> BlockProcessorResult {
+    let blockhash = block.blockhash;
+    let parent_slot = block.parent_slot;
+
+    let leader_id = block
+        .rewards
+        .and_then(|rewards| {
+            rewards
+                .rewards
+                .into_iter()
+                .find(|reward| reward.reward_type == yellowstone_grpc_proto::prelude::RewardType::Fee as i32)
+                .map(|reward| reward.pubkey)
+        });
+
+    let mut transaction_infos = Vec::with_capacity(block.transactions.len());
+    for tx in block.transactions {
+        let Some(versioned_tx) = tx
+            .transaction
+            .as_ref()
+            .and_then(|t| yellowstone_grpc_proto::convert_from::create_tx_versioned(t.clone()).ok())
+        else {
+            warn!("grpc transaction could not be decoded");
+            continue;
+        };
+        let Some(meta) = tx
+            .meta
+            .and_then(|meta| yellowstone_grpc_proto::convert_from::create_tx_meta(meta).ok())
+        else {
+            continue;
+        };
+
+        let signature = versioned_tx.signatures[0].to_string();
+        let err = meta.status.clone().err();
+        let status = meta.status;
+        let cu_consumed = meta.compute_units_consumed.map(|cu| cu as i64);
+
+        let (cu_requested, prioritization_fees) = decode_compute_budget_instructions(&versioned_tx);
+
+        transaction_infos.push(TransactionInfo {
+            signature,
+            err,
+            status,
+            cu_requested,
+            prioritization_fees,
+            cu_consumed,
+            is_vote: is_simple_vote_transaction(&versioned_tx),
+        });
+    }
+
+    let prioritization_fees =
+        compute_prioritization_fees(transaction_infos.iter().filter(|tx| !tx.is_vote));
+
+    // Account contention tracking runs off the RPC-polled `BlockProcessor::process` path today;
+    // the gRPC path doesn't decode loaded-address writability yet. Make that loud rather than
+    // letting `heavily_writelocked_accounts`/`heavily_readlocked_accounts` silently read empty.
+    ACCOUNT_CONTENTION_GAP_LOGGED.call_once(|| {
+        warn!(
+            "grpc transport does not compute heavily_writelocked_accounts/heavily_readlocked_accounts; \
+             these will always be empty on this block source"
+        );
+    });
+    GRPC_ACCOUNT_CONTENTION_UNAVAILABLE.set(1);
+
+    BlockProcessorResult {
+        invalid_block: false,
+        transaction_infos,
+        leader_id,
+        blockhash,
+        parent_slot,
+        prioritization_fees,
+        heavily_writelocked_accounts: vec![],
+        heavily_readlocked_accounts: vec![],
+    }
+}
+
@@ -5,28 +5,145 @@ use crate::{
         vote_accounts_and_cluster_info_polling::poll_vote_accounts_and_cluster_info,
     },
 };
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use log::warn;
+use prometheus::{opts, register_int_counter_vec, register_int_gauge_vec, IntCounterVec, IntGaugeVec};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_lite_rpc_core::AnyhowJoinHandle;
-use solana_sdk::commitment_config::CommitmentConfig;
-use std::sync::Arc;
+use solana_lite_rpc_core::{
+    block_processor::{BlockProcessorResult, PrioritizationFeesInfo},
+    AnyhowJoinHandle,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, slot_history::Slot};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::time::Instant;
+use tokio_stream::wrappers::BroadcastStream;
+
+lazy_static! {
+    static ref SOURCE_LAST_SLOT_SEEN: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "literpc_rpc_source_last_slot_seen",
+            "Last slot produced by each configured RPC polling source"
+        ),
+        &["source"]
+    )
+    .unwrap();
+    static ref SOURCE_HEALTHY: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "literpc_rpc_source_healthy",
+            "Whether an RPC polling source has produced a slot within its staleness window"
+        ),
+        &["source"]
+    )
+    .unwrap();
+    static ref SOURCE_STREAM_ERRORS: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "literpc_rpc_source_stream_errors",
+            "Number of times an RPC polling source's notifications lagged behind the fan-in; RPC request errors are not counted"
+        ),
+        &["source"]
+    )
+    .unwrap();
+    static ref SOURCE_HEALTH_METRICS: SourceHealthMetrics = SourceHealthMetrics {
+        healthy: &SOURCE_HEALTHY,
+        last_slot_seen: &SOURCE_LAST_SLOT_SEEN,
+        stream_errors: &SOURCE_STREAM_ERRORS,
+    };
+}
+
+/// A window during which a source must produce at least one slot to be considered healthy. A
+/// source that goes quiet for longer than this is demoted: its `*_source_healthy` gauge drops to
+/// 0, but the fan-in otherwise keeps routing around it automatically since a healthy source will
+/// simply keep winning the race for each new slot.
+///
+/// Shared with `grpc_subscription`, which fans in gRPC sources the same way.
+pub(crate) const SOURCE_STALENESS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many recently-seen slots/blockhashes to remember for de-duplication before trimming.
+///
+/// Shared with `grpc_subscription`, which fans in gRPC sources the same way.
+pub(crate) const DEDUP_WATERMARK: u64 = 1024;
+
+/// One prioritized RPC endpoint to poll for slots/blocks. The first source to deliver a given
+/// slot wins; the rest are dropped as duplicates.
+#[derive(Clone)]
+pub struct RpcSourceConfig {
+    pub rpc_client: Arc<RpcClient>,
+    /// Used only to label Prometheus metrics for this source.
+    pub label: String,
+}
+
+impl RpcSourceConfig {
+    pub fn new(label: String, rpc_client: Arc<RpcClient>) -> Self {
+        Self { rpc_client, label }
+    }
+}
 
 pub fn create_json_rpc_polling_subscription(
-    rpc_client: Arc<RpcClient>,
+    sources: Vec<RpcSourceConfig>,
 ) -> anyhow::Result<(EndpointStreaming, Vec<AnyhowJoinHandle>)> {
+    if sources.is_empty() {
+        anyhow::bail!("create_json_rpc_polling_subscription requires at least one RPC source");
+    }
+
     let (slot_sx, slot_notifier) = tokio::sync::broadcast::channel(10);
     let (block_sx, blocks_notifier) = tokio::sync::broadcast::channel(10);
     let (cluster_info_sx, cluster_info_notifier) = tokio::sync::broadcast::channel(10);
     let (va_sx, vote_account_notifier) = tokio::sync::broadcast::channel(10);
+    let (prioritization_fees_sx, prioritization_fees_notifier) =
+        tokio::sync::broadcast::channel(10);
+
+    let mut endpoint_tasks = vec![];
+    let mut per_source_slot_receivers = vec![];
+    let mut per_source_block_receivers = vec![];
+    let mut labels = vec![];
+
+    for source in &sources {
+        let (source_slot_sx, source_slot_notifier) = tokio::sync::broadcast::channel(10);
+        let (source_block_sx, source_block_notifier) = tokio::sync::broadcast::channel(10);
+        // `poll_block` wants somewhere to put its own derived fee notifications, but the
+        // fan-in stage below re-derives a single deduplicated `prioritization_fees_notifier`
+        // from the winning block of each source, so the per-source copy is unused.
+        let (source_fees_sx, _source_fees_notifier) = tokio::sync::broadcast::channel(10);
+
+        let mut slot_tasks = poll_slots(
+            source.rpc_client.clone(),
+            CommitmentConfig::processed(),
+            source_slot_sx,
+        )?;
+        endpoint_tasks.append(&mut slot_tasks);
 
-    let mut endpoint_tasks =
-        poll_slots(rpc_client.clone(), CommitmentConfig::processed(), slot_sx)?;
+        let mut block_tasks = poll_block(
+            source.rpc_client.clone(),
+            source_block_sx,
+            source_fees_sx,
+            source_slot_notifier.resubscribe(),
+        );
+        endpoint_tasks.append(&mut block_tasks);
 
-    let mut block_polling_tasks =
-        poll_block(rpc_client.clone(), block_sx, slot_notifier.resubscribe());
-    endpoint_tasks.append(&mut block_polling_tasks);
+        per_source_slot_receivers.push(source_slot_notifier);
+        per_source_block_receivers.push(source_block_notifier);
+        labels.push(source.label.clone());
+    }
 
-    let cluster_info_polling =
-        poll_vote_accounts_and_cluster_info(rpc_client, cluster_info_sx, va_sx);
+    endpoint_tasks.push(fan_in_dedup_slots(
+        per_source_slot_receivers,
+        labels.clone(),
+        slot_sx,
+        &SOURCE_HEALTH_METRICS,
+    ));
+    endpoint_tasks.push(fan_in_dedup_blocks(
+        per_source_block_receivers,
+        labels,
+        block_sx,
+        prioritization_fees_sx,
+        &SOURCE_HEALTH_METRICS,
+    ));
+
+    // Cluster/vote-account info is cheap and idempotent enough that fanning it in isn't worth
+    // the complexity; poll it from the highest-priority source only.
+    let primary = sources[0].rpc_client.clone();
+    let cluster_info_polling = poll_vote_accounts_and_cluster_info(primary, cluster_info_sx, va_sx);
     endpoint_tasks.push(cluster_info_polling);
 
     let streamers = EndpointStreaming {
@@ -34,6 +151,130 @@ pub fn create_json_rpc_polling_subscription(
         slot_notifier,
         cluster_info_notifier,
         vote_account_notifier,
+        prioritization_fees_notifier,
     };
     Ok((streamers, endpoint_tasks))
 }
+
+/// The per-source health/error gauges a fan-in stage reports into, factored out so
+/// `fan_in_dedup_slots`/`fan_in_dedup_blocks` can be shared between the RPC-polling and gRPC
+/// transports, which otherwise track identical source-health/de-dup bookkeeping under separate
+/// metric names (`literpc_rpc_source_*` vs `literpc_grpc_source_*`).
+pub(crate) struct SourceHealthMetrics {
+    pub healthy: &'static IntGaugeVec,
+    pub last_slot_seen: &'static IntGaugeVec,
+    pub stream_errors: &'static IntCounterVec,
+}
+
+pub(crate) fn mark_source_seen(
+    labels: &[String],
+    idx: usize,
+    last_seen_at: &mut [Instant],
+    metrics: &SourceHealthMetrics,
+) {
+    last_seen_at[idx] = Instant::now();
+    for (i, seen_at) in last_seen_at.iter().enumerate() {
+        let healthy = seen_at.elapsed() <= SOURCE_STALENESS_TIMEOUT;
+        metrics
+            .healthy
+            .with_label_values(&[&labels[i]])
+            .set(healthy as i64);
+    }
+}
+
+pub(crate) fn fan_in_dedup_slots(
+    receivers: Vec<tokio::sync::broadcast::Receiver<Slot>>,
+    labels: Vec<String>,
+    out: tokio::sync::broadcast::Sender<Slot>,
+    metrics: &'static SourceHealthMetrics,
+) -> AnyhowJoinHandle {
+    tokio::spawn(async move {
+        let mut seen: HashSet<Slot> = HashSet::new();
+        let mut last_seen_at = vec![Instant::now(); receivers.len()];
+        let mut merged = futures::stream::select_all(
+            receivers
+                .into_iter()
+                .enumerate()
+                .map(|(idx, rx)| BroadcastStream::new(rx).map(move |item| (idx, item))),
+        );
+
+        while let Some((idx, item)) = merged.next().await {
+            match item {
+                Ok(slot) => {
+                    mark_source_seen(&labels, idx, &mut last_seen_at, metrics);
+                    metrics
+                        .last_slot_seen
+                        .with_label_values(&[&labels[idx]])
+                        .set(slot as i64);
+                    if seen.insert(slot) {
+                        let _ = out.send(slot);
+                    }
+                    if seen.len() as u64 > DEDUP_WATERMARK {
+                        seen.retain(|&s| s + DEDUP_WATERMARK >= slot);
+                    }
+                }
+                Err(_lagged) => {
+                    metrics.stream_errors.with_label_values(&[&labels[idx]]).inc();
+                }
+            }
+        }
+        warn!("all slot polling sources have stopped producing");
+        Ok(())
+    })
+}
+
+pub(crate) fn fan_in_dedup_blocks(
+    receivers: Vec<tokio::sync::broadcast::Receiver<BlockProcessorResult>>,
+    labels: Vec<String>,
+    out: tokio::sync::broadcast::Sender<BlockProcessorResult>,
+    prioritization_fees_out: tokio::sync::broadcast::Sender<BlockProcessorResult>,
+    metrics: &'static SourceHealthMetrics,
+) -> AnyhowJoinHandle {
+    tokio::spawn(async move {
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut last_seen_at = vec![Instant::now(); receivers.len()];
+        let mut merged = futures::stream::select_all(
+            receivers
+                .into_iter()
+                .enumerate()
+                .map(|(idx, rx)| BroadcastStream::new(rx).map(move |item| (idx, item))),
+        );
+
+        while let Some((idx, item)) = merged.next().await {
+            match item {
+                Ok(block) => {
+                    mark_source_seen(&labels, idx, &mut last_seen_at, metrics);
+                    if seen.insert(block.blockhash.clone()) {
+                        let _ = prioritization_fees_out.send(block.clone());
+                        let _ = out.send(block);
+                    }
+                    if seen.len() as u64 > DEDUP_WATERMARK {
+                        // Blockhashes don't carry an obvious ordering, so bound memory by
+                        // clearing the set outright once it grows past the watermark; a
+                        // handful of duplicate forwards right after a clear is harmless.
+                        seen.clear();
+                    }
+                }
+                Err(_lagged) => {
+                    metrics.stream_errors.with_label_values(&[&labels[idx]]).inc();
+                }
+            }
+        }
+        warn!("all block polling sources have stopped producing");
+        Ok(())
+    })
+}
+
+/// Adapts `EndpointStreaming::prioritization_fees_notifier` into the per-block fee stream a
+/// `blockPrioritizationFeesSubscribe` websocket pubsub method would forward to subscribers.
+///
+/// This checkout doesn't include the pubsub server (`lite_rpc::bridge`/`rpc`, where the other
+/// `*Subscribe` methods such as `blockSubscribe` are registered), so the method itself can't be
+/// wired up here; this is the transport-agnostic piece that belongs in `cluster-endpoints`,
+/// ready for that server to register once it subscribes to it.
+pub fn prioritization_fees_subscription_stream(
+    prioritization_fees_notifier: tokio::sync::broadcast::Receiver<BlockProcessorResult>,
+) -> impl futures::Stream<Item = PrioritizationFeesInfo> {
+    BroadcastStream::new(prioritization_fees_notifier)
+        .filter_map(|item| async move { item.ok().map(|block| block.prioritization_fees) })
+}
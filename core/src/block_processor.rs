@@ -22,14 +22,20 @@ use crate::block_store::{BlockInformation, BlockStore};
 pub struct BlockProcessor {
     rpc_client: Arc<RpcClient>,
     block_store: Option<BlockStore>,
+    account_contention_threshold: AccountContentionThreshold,
+    store_vote_transactions: bool,
 }
 
+#[derive(Clone)]
 pub struct BlockProcessorResult {
     pub invalid_block: bool,
     pub transaction_infos: Vec<TransactionInfo>,
     pub leader_id: Option<String>,
     pub blockhash: String,
     pub parent_slot: Slot,
+    pub prioritization_fees: PrioritizationFeesInfo,
+    pub heavily_writelocked_accounts: Vec<AccountContention>,
+    pub heavily_readlocked_accounts: Vec<AccountContention>,
 }
 
 impl BlockProcessorResult {
@@ -40,10 +46,270 @@ impl BlockProcessorResult {
             leader_id: None,
             blockhash: String::new(),
             parent_slot: 0,
+            prioritization_fees: PrioritizationFeesInfo::default(),
+            heavily_writelocked_accounts: vec![],
+            heavily_readlocked_accounts: vec![],
+        }
+    }
+}
+
+/// How contended an account was across a block: how many transactions locked it, and how much
+/// compute those transactions requested.
+#[derive(Clone, Debug, Default)]
+pub struct AccountContention {
+    pub account: String,
+    pub tx_count: u64,
+    pub cu_requested: u64,
+}
+
+/// Threshold past which an account is reported as "heavily locked" in a block: either enough
+/// transactions touched it, or they requested enough compute between them.
+#[derive(Clone, Copy, Debug)]
+pub struct AccountContentionThreshold {
+    pub min_tx_count: u64,
+    pub min_cu_requested: u64,
+}
+
+impl Default for AccountContentionThreshold {
+    fn default() -> Self {
+        Self {
+            min_tx_count: 20,
+            min_cu_requested: 2_000_000,
+        }
+    }
+}
+
+#[derive(Default)]
+struct AccountContentionAcc {
+    tx_count: u64,
+    cu_requested: u64,
+}
+
+/// Resolves each static account key's writability, plus any address-lookup-table keys
+/// resolved for v0 messages (reported separately on the transaction meta).
+fn account_writability(
+    message: &solana_sdk::message::VersionedMessage,
+    loaded_addresses: &OptionSerializer<solana_transaction_status::UiLoadedAddresses>,
+) -> Vec<(String, bool)> {
+    let mut accounts: Vec<(String, bool)> = message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .map(|(i, key)| (key.to_string(), message.is_writable(i)))
+        .collect();
+
+    if let OptionSerializer::Some(loaded) = loaded_addresses {
+        accounts.extend(loaded.writable.iter().cloned().map(|a| (a, true)));
+        accounts.extend(loaded.readonly.iter().cloned().map(|a| (a, false)));
+    }
+
+    accounts
+}
+
+/// Decodes a transaction's compute-budget instructions into `(cu_requested, prioritization_fees)`.
+/// When a deprecated `RequestUnitsDeprecated` instruction is present, its values take precedence
+/// over `SetComputeUnitLimit`/`SetComputeUnitPrice`; it folds both values into a single instruction.
+pub fn decode_compute_budget_instructions(
+    tx: &solana_sdk::transaction::VersionedTransaction,
+) -> (Option<i64>, Option<i64>) {
+    let legacy_compute_budget = tx.message.instructions().iter().find_map(|i| {
+        if i.program_id(tx.message.static_account_keys())
+            .eq(&compute_budget::id())
+        {
+            if let Ok(ComputeBudgetInstruction::RequestUnitsDeprecated {
+                units,
+                additional_fee,
+            }) = try_from_slice_unchecked(i.data.as_slice())
+            {
+                return Some((units, additional_fee));
+            }
         }
+        None
+    });
+
+    let mut cu_requested = tx.message.instructions().iter().find_map(|i| {
+        if i.program_id(tx.message.static_account_keys())
+            .eq(&compute_budget::id())
+        {
+            if let Ok(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) =
+                try_from_slice_unchecked(i.data.as_slice())
+            {
+                return Some(limit as i64);
+            }
+        }
+        None
+    });
+
+    let mut prioritization_fees = tx.message.instructions().iter().find_map(|i| {
+        if i.program_id(tx.message.static_account_keys())
+            .eq(&compute_budget::id())
+        {
+            if let Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) =
+                try_from_slice_unchecked(i.data.as_slice())
+            {
+                return Some(price as i64);
+            }
+        }
+        None
+    });
+
+    if let Some((units, additional_fee)) = legacy_compute_budget {
+        cu_requested = Some(units as i64);
+        if additional_fee > 0 {
+            prioritization_fees = Some(((units * 1000) / additional_fee).into());
+        }
+    }
+
+    (cu_requested, prioritization_fees)
+}
+
+fn heavily_locked_accounts(
+    acc: std::collections::HashMap<String, AccountContentionAcc>,
+    threshold: &AccountContentionThreshold,
+) -> Vec<AccountContention> {
+    let mut contended: Vec<AccountContention> = acc
+        .into_iter()
+        .filter(|(_, acc)| {
+            acc.tx_count >= threshold.min_tx_count || acc.cu_requested >= threshold.min_cu_requested
+        })
+        .map(|(account, acc)| AccountContention {
+            account,
+            tx_count: acc.tx_count,
+            cu_requested: acc.cu_requested,
+        })
+        .collect();
+
+    contended.sort_unstable_by(|a, b| {
+        b.tx_count
+            .cmp(&a.tx_count)
+            .then_with(|| b.cu_requested.cmp(&a.cu_requested))
+    });
+
+    contended
+}
+
+/// Percentile steps (in whole percent) that `PrioritizationFeesInfo` reports fees for.
+pub const PRIORITIZATION_FEE_PERCENTILES: [u8; 21] = [
+    0, 5, 10, 15, 20, 25, 30, 35, 40, 45, 50, 55, 60, 65, 70, 75, 80, 85, 90, 95, 100,
+];
+
+/// Per-block prioritization-fee percentiles, computed over non-vote transactions only.
+#[derive(Clone, Debug, Default)]
+pub struct PrioritizationFeesInfo {
+    /// `(percentile, fee)` pairs, one fee value per transaction weighted equally.
+    pub percentiles_by_tx_count: Vec<(u8, i64)>,
+    /// `(percentile, fee)` pairs, weighted by each transaction's `cu_consumed`.
+    pub percentiles_by_cu_consumed: Vec<(u8, i64)>,
+    pub cu_consumed: u64,
+    pub tx_count: usize,
+}
+
+/// Computes `PrioritizationFeesInfo` over the given non-vote transactions.
+///
+/// `prioritization_fees`/`cu_consumed` default to `0` for transactions that didn't report
+/// them, so every transaction still contributes to both percentile series.
+pub fn compute_prioritization_fees<'a>(
+    non_vote_txs: impl Iterator<Item = &'a TransactionInfo> + Clone,
+) -> PrioritizationFeesInfo {
+    let mut by_fee: Vec<i64> = non_vote_txs
+        .clone()
+        .map(|tx| tx.prioritization_fees.unwrap_or(0))
+        .collect();
+    let tx_count = by_fee.len();
+
+    if tx_count == 0 {
+        return PrioritizationFeesInfo::default();
+    }
+
+    by_fee.sort_unstable();
+
+    let percentiles_by_tx_count = PRIORITIZATION_FEE_PERCENTILES
+        .iter()
+        .map(|&p| {
+            let idx = (p as usize * (tx_count - 1)) / 100;
+            (p, by_fee[idx])
+        })
+        .collect();
+
+    let mut by_fee_and_cu: Vec<(i64, u64)> = non_vote_txs
+        .map(|tx| {
+            (
+                tx.prioritization_fees.unwrap_or(0),
+                tx.cu_consumed.unwrap_or(0) as u64,
+            )
+        })
+        .collect();
+    by_fee_and_cu.sort_unstable_by_key(|(fee, _)| *fee);
+
+    let cu_consumed: u64 = by_fee_and_cu
+        .iter()
+        .fold(0u64, |acc, (_, cu)| acc.saturating_add(*cu));
+
+    let percentiles_by_cu_consumed = if cu_consumed == 0 {
+        PRIORITIZATION_FEE_PERCENTILES
+            .iter()
+            .map(|&p| (p, 0))
+            .collect()
+    } else {
+        let mut cumulative_cu = 0u64;
+        let mut current_fee = by_fee_and_cu.first().map(|(fee, _)| *fee).unwrap_or(0);
+        let mut iter = by_fee_and_cu.iter();
+        PRIORITIZATION_FEE_PERCENTILES
+            .iter()
+            .map(|&p| {
+                let target = ((p as u128 * cu_consumed as u128) / 100) as u64;
+                // Only pull in more transactions once the accumulated cu so far no longer
+                // satisfies this (non-decreasing) percentile's target; a single transaction's cu
+                // can clear several percentile thresholds at once.
+                while cumulative_cu < target {
+                    match iter.next() {
+                        Some((fee, cu)) => {
+                            cumulative_cu = cumulative_cu.saturating_add(*cu);
+                            current_fee = *fee;
+                        }
+                        None => break,
+                    }
+                }
+                (p, current_fee)
+            })
+            .collect()
+    };
+
+    PrioritizationFeesInfo {
+        percentiles_by_tx_count,
+        percentiles_by_cu_consumed,
+        cu_consumed,
+        tx_count,
+    }
+}
+
+/// A simple vote transaction has exactly one instruction, addressed to the vote program,
+/// whose first four bytes are one of the vote-instruction discriminants below.
+pub fn is_simple_vote_transaction(tx: &solana_sdk::transaction::VersionedTransaction) -> bool {
+    const VOTE_INSTRUCTION_DISCRIMINANTS: [u32; 8] = [2, 6, 8, 9, 12, 13, 14, 15];
+
+    let instructions = tx.message.instructions();
+    if instructions.len() != 1 {
+        return false;
+    }
+    let instruction = &instructions[0];
+    if !instruction
+        .program_id(tx.message.static_account_keys())
+        .eq(&solana_sdk::vote::program::id())
+    {
+        return false;
     }
+    instruction
+        .data
+        .get(0..4)
+        .map(|bytes| {
+            let discriminant = u32::from_le_bytes(bytes.try_into().unwrap());
+            VOTE_INSTRUCTION_DISCRIMINANTS.contains(&discriminant)
+        })
+        .unwrap_or(false)
 }
 
+#[derive(Clone)]
 pub struct TransactionInfo {
     pub signature: String,
     pub err: Option<TransactionError>,
@@ -51,6 +317,7 @@ pub struct TransactionInfo {
     pub cu_requested: Option<i64>,
     pub prioritization_fees: Option<i64>,
     pub cu_consumed: Option<i64>,
+    pub is_vote: bool,
 }
 
 impl BlockProcessor {
@@ -58,26 +325,43 @@ impl BlockProcessor {
         Self {
             rpc_client,
             block_store,
+            account_contention_threshold: AccountContentionThreshold::default(),
+            store_vote_transactions: true,
         }
     }
 
+    pub fn with_account_contention_threshold(
+        mut self,
+        account_contention_threshold: AccountContentionThreshold,
+    ) -> Self {
+        self.account_contention_threshold = account_contention_threshold;
+        self
+    }
+
+    /// When `false`, vote transactions are dropped from `transaction_infos` to keep memory down
+    /// on busy validators. Fee percentiles exclude vote transactions either way, and account
+    /// contention still counts them.
+    pub fn with_store_vote_transactions(mut self, store_vote_transactions: bool) -> Self {
+        self.store_vote_transactions = store_vote_transactions;
+        self
+    }
+
     pub async fn process(
         &self,
         slot: Slot,
         commitment_config: CommitmentConfig,
     ) -> anyhow::Result<BlockProcessorResult> {
+        let block_config = RpcBlockConfig {
+            transaction_details: Some(TransactionDetails::Full),
+            commitment: Some(commitment_config),
+            max_supported_transaction_version: Some(0),
+            encoding: Some(UiTransactionEncoding::Base64),
+            rewards: Some(true),
+        };
+
         let block = self
             .rpc_client
-            .get_block_with_config(
-                slot,
-                RpcBlockConfig {
-                    transaction_details: Some(TransactionDetails::Full),
-                    commitment: Some(commitment_config),
-                    max_supported_transaction_version: Some(0),
-                    encoding: Some(UiTransactionEncoding::Base64),
-                    rewards: Some(true),
-                },
-            )
+            .get_block_with_config(slot, block_config)
             .await?;
 
         let Some(block_height) = block.block_height else {
@@ -108,8 +392,12 @@ impl BlockProcessor {
 
         let mut transaction_infos = vec![];
         transaction_infos.reserve(transactions.len());
+        let mut writelock_acc: std::collections::HashMap<String, AccountContentionAcc> =
+            std::collections::HashMap::new();
+        let mut readlock_acc: std::collections::HashMap<String, AccountContentionAcc> =
+            std::collections::HashMap::new();
         for tx in transactions {
-            let Some(UiTransactionStatusMeta { err, status, compute_units_consumed ,.. }) = tx.meta else {
+            let Some(UiTransactionStatusMeta { err, status, compute_units_consumed, loaded_addresses, .. }) = tx.meta else {
                 info!("tx with no meta");
                 continue;
             };
@@ -127,53 +415,18 @@ impl BlockProcessor {
                 _ => None,
             };
 
-            let legacy_compute_budget = tx.message.instructions().iter().find_map(|i| {
-                if i.program_id(tx.message.static_account_keys())
-                    .eq(&compute_budget::id())
-                {
-                    if let Ok(ComputeBudgetInstruction::RequestUnitsDeprecated {
-                        units,
-                        additional_fee,
-                    }) = try_from_slice_unchecked(i.data.as_slice())
-                    {
-                        return Some((units, additional_fee));
-                    }
-                }
-                None
-            });
-
-            let mut cu_requested = tx.message.instructions().iter().find_map(|i| {
-                if i.program_id(tx.message.static_account_keys())
-                    .eq(&compute_budget::id())
-                {
-                    if let Ok(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) =
-                        try_from_slice_unchecked(i.data.as_slice())
-                    {
-                        return Some(limit as i64);
-                    }
-                }
-                None
-            });
-
-            let mut prioritization_fees = tx.message.instructions().iter().find_map(|i| {
-                if i.program_id(tx.message.static_account_keys())
-                    .eq(&compute_budget::id())
-                {
-                    if let Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) =
-                        try_from_slice_unchecked(i.data.as_slice())
-                    {
-                        return Some(price as i64);
-                    }
-                }
-                None
-            });
+            let (cu_requested, prioritization_fees) = decode_compute_budget_instructions(&tx);
 
-            if let Some((units, additional_fee)) = legacy_compute_budget {
-                cu_requested = Some(units as i64);
-                if additional_fee > 0 {
-                    prioritization_fees = Some(((units * 1000) / additional_fee).into())
-                }
-            };
+            let cu_for_account = cu_requested.unwrap_or(0).max(0) as u64;
+            for (account, is_writable) in account_writability(&tx.message, &loaded_addresses) {
+                let acc = if is_writable {
+                    writelock_acc.entry(account).or_default()
+                } else {
+                    readlock_acc.entry(account).or_default()
+                };
+                acc.tx_count = acc.tx_count.saturating_add(1);
+                acc.cu_requested = acc.cu_requested.saturating_add(cu_for_account);
+            }
 
             transaction_infos.push(TransactionInfo {
                 signature,
@@ -182,9 +435,22 @@ impl BlockProcessor {
                 cu_requested,
                 prioritization_fees,
                 cu_consumed,
+                is_vote: is_simple_vote_transaction(&tx),
             });
         }
 
+        let prioritization_fees =
+            compute_prioritization_fees(transaction_infos.iter().filter(|tx| !tx.is_vote));
+
+        if !self.store_vote_transactions {
+            transaction_infos.retain(|tx| !tx.is_vote);
+        }
+
+        let heavily_writelocked_accounts =
+            heavily_locked_accounts(writelock_acc, &self.account_contention_threshold);
+        let heavily_readlocked_accounts =
+            heavily_locked_accounts(readlock_acc, &self.account_contention_threshold);
+
         let leader_id = if let Some(rewards) = block.rewards {
             rewards
                 .iter()
@@ -200,6 +466,9 @@ impl BlockProcessor {
             leader_id,
             blockhash,
             parent_slot,
+            prioritization_fees,
+            heavily_writelocked_accounts,
+            heavily_readlocked_accounts,
         })
     }
 
@@ -221,3 +490,133 @@ impl BlockProcessor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        instruction::Instruction, message::Message, pubkey::Pubkey, signature::Keypair,
+        signer::Signer, transaction::Transaction,
+    };
+
+    fn vote_transaction_with_discriminant(discriminant: u32) -> solana_sdk::transaction::VersionedTransaction {
+        let payer = Keypair::new();
+        let mut data = discriminant.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0u8; 4]);
+        let instruction = Instruction {
+            program_id: solana_sdk::vote::program::id(),
+            accounts: vec![],
+            data,
+        };
+        let message = Message::new(&[instruction], Some(&payer.pubkey()));
+        solana_sdk::transaction::VersionedTransaction::from(Transaction::new_unsigned(message))
+    }
+
+    #[test]
+    fn recognizes_tower_sync_as_simple_vote() {
+        // TowerSync, added to replace the older UpdateVoteState variants.
+        let tx = vote_transaction_with_discriminant(14);
+        assert!(is_simple_vote_transaction(&tx));
+    }
+
+    #[test]
+    fn recognizes_update_vote_state_as_simple_vote() {
+        let tx = vote_transaction_with_discriminant(8);
+        assert!(is_simple_vote_transaction(&tx));
+    }
+
+    #[test]
+    fn rejects_non_vote_discriminant() {
+        // 10 is AuthorizeWithSeed, not a simple vote instruction.
+        let tx = vote_transaction_with_discriminant(10);
+        assert!(!is_simple_vote_transaction(&tx));
+    }
+
+    fn non_vote_tx(prioritization_fees: i64, cu_consumed: i64) -> TransactionInfo {
+        TransactionInfo {
+            signature: String::new(),
+            err: None,
+            status: Ok(()),
+            cu_requested: None,
+            prioritization_fees: Some(prioritization_fees),
+            cu_consumed: Some(cu_consumed),
+            is_vote: false,
+        }
+    }
+
+    #[test]
+    fn cu_weighted_percentile_reports_the_transaction_that_crosses_the_target() {
+        // One large transaction's cu clears several percentile targets at once; every one of
+        // those percentiles should report its fee, not the next transaction's.
+        let txs = vec![non_vote_tx(10, 900), non_vote_tx(20, 100)];
+        let info = compute_prioritization_fees(txs.iter());
+        let fee_at = |p: u8| {
+            info.percentiles_by_cu_consumed
+                .iter()
+                .find(|(pct, _)| *pct == p)
+                .unwrap()
+                .1
+        };
+        assert_eq!(fee_at(5), 10);
+        assert_eq!(fee_at(50), 10);
+        assert_eq!(fee_at(90), 10);
+        assert_eq!(fee_at(95), 20);
+        assert_eq!(fee_at(100), 20);
+    }
+
+    #[test]
+    fn heavily_locked_accounts_filters_below_threshold() {
+        let threshold = AccountContentionThreshold {
+            min_tx_count: 5,
+            min_cu_requested: 1_000,
+        };
+        let mut acc = std::collections::HashMap::new();
+        acc.insert(
+            "hot".to_string(),
+            AccountContentionAcc {
+                tx_count: 10,
+                cu_requested: 500,
+            },
+        );
+        acc.insert(
+            "cold".to_string(),
+            AccountContentionAcc {
+                tx_count: 1,
+                cu_requested: 10,
+            },
+        );
+
+        let contended = heavily_locked_accounts(acc, &threshold);
+
+        assert_eq!(contended.len(), 1);
+        assert_eq!(contended[0].account, "hot");
+    }
+
+    #[test]
+    fn heavily_locked_accounts_sorts_most_contended_first() {
+        let threshold = AccountContentionThreshold {
+            min_tx_count: 1,
+            min_cu_requested: u64::MAX,
+        };
+        let mut acc = std::collections::HashMap::new();
+        acc.insert(
+            "busier".to_string(),
+            AccountContentionAcc {
+                tx_count: 10,
+                cu_requested: 0,
+            },
+        );
+        acc.insert(
+            "quieter".to_string(),
+            AccountContentionAcc {
+                tx_count: 2,
+                cu_requested: 0,
+            },
+        );
+
+        let contended = heavily_locked_accounts(acc, &threshold);
+
+        assert_eq!(contended[0].account, "busier");
+        assert_eq!(contended[1].account, "quieter");
+    }
+}
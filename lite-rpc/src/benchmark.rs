@@ -0,0 +1,320 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use clap::{Parser, ValueEnum};
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use log::{info, warn};
+use prometheus::{opts, register_gauge, register_int_gauge, Gauge, IntGauge};
+use solana_client::{nonblocking::tpu_client::TpuClient, tpu_client::TpuClientConfig};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use solana_lite_rpc_cluster_endpoints::{
+    endpoint_stremers::EndpointStreaming,
+    grpc_subscription::{create_grpc_subscription, GrpcSourceConfig},
+    json_rpc_subscription::{create_json_rpc_polling_subscription, RpcSourceConfig},
+};
+use tokio::time::Instant;
+
+lazy_static! {
+    static ref BENCH_TPS: Gauge =
+        register_gauge!(opts!("literpc_bench_tps", "Rolling landed transactions per second")).unwrap();
+    static ref BENCH_MEDIAN_CONFIRMATION_MS: Gauge = register_gauge!(opts!(
+        "literpc_bench_median_confirmation_latency_ms",
+        "Median time between submission and landing, in milliseconds"
+    ))
+    .unwrap();
+    static ref BENCH_LAND_RATE: Gauge = register_gauge!(opts!(
+        "literpc_bench_land_rate",
+        "Fraction of submitted transactions that landed"
+    ))
+    .unwrap();
+    static ref BENCH_SUBMITTED: IntGauge =
+        register_int_gauge!(opts!("literpc_bench_submitted", "Total transactions submitted")).unwrap();
+    static ref BENCH_LANDED: IntGauge =
+        register_int_gauge!(opts!("literpc_bench_landed", "Total transactions landed")).unwrap();
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq)]
+pub enum BenchmarkTransport {
+    Polling,
+    Grpc,
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "lite-rpc benchmark")]
+pub struct BenchmarkArgs {
+    #[arg(short, long, default_value_t = String::from("http://127.0.0.1:8899"))]
+    pub rpc_addr: String,
+
+    #[arg(short, long, default_value_t = String::from("ws://127.0.0.1:8900"))]
+    pub ws_addr: String,
+
+    #[arg(long)]
+    pub identity_keypair: String,
+
+    /// Number of independent payer keypairs submitting self-transfers concurrently.
+    #[arg(long, default_value_t = 8)]
+    pub num_payers: usize,
+
+    /// Target submitted transactions per second, split evenly across payers.
+    #[arg(long, default_value_t = 100)]
+    pub target_tps: u64,
+
+    /// Number of leaders to fan a transaction out to, same knob the bridge uses.
+    #[arg(long, default_value_t = 4)]
+    pub fanout_size: u64,
+
+    /// How long to run the benchmark for.
+    #[arg(long, default_value_t = 60)]
+    pub duration_secs: u64,
+
+    /// Which block source to confirm landed transactions against.
+    #[arg(long, value_enum, default_value = "polling")]
+    pub transport: BenchmarkTransport,
+
+    /// Yellowstone gRPC endpoint to subscribe to; required when `--transport grpc` is set.
+    #[arg(long)]
+    pub grpc_url: Option<String>,
+
+    /// Optional x-token for the gRPC endpoint above.
+    #[arg(long)]
+    pub grpc_x_token: Option<String>,
+}
+
+struct InFlightTx {
+    submitted_at: Instant,
+}
+
+pub async fn run_benchmark(args: BenchmarkArgs) -> anyhow::Result<()> {
+    let rpc_client = Arc::new(RpcClient::new(args.rpc_addr.clone()));
+
+    let identity_file = tokio::fs::read_to_string(&args.identity_keypair)
+        .await
+        .expect("cannot find the identity file provided");
+    let identity_bytes: Vec<u8> = serde_json::from_str(&identity_file)?;
+    let identity = Keypair::from_bytes(&identity_bytes)?;
+
+    let payers: Vec<Keypair> = (0..args.num_payers).map(|_| Keypair::new()).collect();
+    fund_payers(&rpc_client, &identity, &payers).await?;
+
+    let tpu_client = TpuClient::new(
+        "literpc-benchmark",
+        rpc_client.clone(),
+        args.ws_addr.as_str(),
+        TpuClientConfig {
+            fanout_slots: args.fanout_size,
+        },
+    )
+    .await?;
+
+    let (streamers, _block_tasks): (EndpointStreaming, _) = match args.transport {
+        BenchmarkTransport::Polling => create_json_rpc_polling_subscription(vec![RpcSourceConfig::new(
+            "benchmark".to_string(),
+            rpc_client.clone(),
+        )])?,
+        BenchmarkTransport::Grpc => {
+            let grpc_url = args
+                .grpc_url
+                .clone()
+                .expect("--grpc-url is required when --transport grpc is set");
+            create_grpc_subscription(vec![GrpcSourceConfig::new(
+                "benchmark".to_string(),
+                grpc_url,
+                args.grpc_x_token.clone(),
+            )])?
+        }
+    };
+    let mut blocks_notifier = streamers.blocks_notifier;
+
+    let in_flight: Arc<DashMap<String, InFlightTx>> = Arc::new(DashMap::new());
+    let submitted = Arc::new(AtomicU64::new(0));
+    let landed = Arc::new(AtomicU64::new(0));
+    let latencies_ms: Arc<tokio::sync::Mutex<Vec<u64>>> = Arc::new(tokio::sync::Mutex::new(vec![]));
+
+    let confirmation_task = tokio::spawn({
+        let in_flight = in_flight.clone();
+        let landed = landed.clone();
+        let latencies_ms = latencies_ms.clone();
+        async move {
+            while let Ok(block) = blocks_notifier.recv().await {
+                for tx in block.transaction_infos {
+                    if let Some((_, in_flight_tx)) = in_flight.remove(&tx.signature) {
+                        landed.fetch_add(1, Ordering::Relaxed);
+                        let latency_ms = in_flight_tx.submitted_at.elapsed().as_millis() as u64;
+                        latencies_ms.lock().await.push(latency_ms);
+                    }
+                }
+            }
+        }
+    });
+
+    let reporting_task = tokio::spawn({
+        let submitted = submitted.clone();
+        let landed = landed.clone();
+        let latencies_ms = latencies_ms.clone();
+        async move {
+            let mut report_interval = tokio::time::interval(Duration::from_secs(1));
+            let mut last_landed = 0u64;
+            let mut last_tick = Instant::now();
+            loop {
+                report_interval.tick().await;
+                last_landed = report_progress(&submitted, &landed, &latencies_ms, last_landed, &mut last_tick).await;
+            }
+        }
+    });
+
+    let per_payer_interval =
+        Duration::from_secs_f64(args.num_payers as f64 / args.target_tps.max(1) as f64);
+
+    let started_at = Instant::now();
+    let deadline = started_at + Duration::from_secs(args.duration_secs);
+
+    let mut blockhash = rpc_client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+        .await?
+        .0;
+    let mut last_blockhash_refresh = Instant::now();
+
+    while Instant::now() < deadline {
+        if last_blockhash_refresh.elapsed() > Duration::from_secs(5) {
+            blockhash = rpc_client
+                .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+                .await?
+                .0;
+            last_blockhash_refresh = Instant::now();
+        }
+
+        for payer in &payers {
+            let tx = self_transfer_transaction(payer, blockhash);
+            let signature = tx.signatures[0].to_string();
+            in_flight.insert(
+                signature,
+                InFlightTx {
+                    submitted_at: Instant::now(),
+                },
+            );
+            submitted.fetch_add(1, Ordering::Relaxed);
+            if !tpu_client.send_transaction(&tx) {
+                warn!("failed to submit benchmark transaction");
+            }
+        }
+
+        tokio::time::sleep(per_payer_interval).await;
+    }
+
+    // drain remaining confirmations for a few seconds before the final report
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    confirmation_task.abort();
+    reporting_task.abort();
+    // the final report's tps is the average over the whole run, drain period included
+    let mut last_tick = started_at;
+    report_progress(&submitted, &landed, &latencies_ms, 0, &mut last_tick).await;
+
+    Ok(())
+}
+
+/// Reports current benchmark progress and returns the landed count observed this call, so the
+/// caller can pass it back in as `last_landed` next time to derive a rolling TPS.
+async fn report_progress(
+    submitted: &Arc<AtomicU64>,
+    landed: &Arc<AtomicU64>,
+    latencies_ms: &Arc<tokio::sync::Mutex<Vec<u64>>>,
+    last_landed: u64,
+    last_tick: &mut Instant,
+) -> u64 {
+    let submitted_count = submitted.load(Ordering::Relaxed);
+    let landed_count = landed.load(Ordering::Relaxed);
+
+    let land_rate = if submitted_count == 0 {
+        0.0
+    } else {
+        landed_count as f64 / submitted_count as f64
+    };
+
+    let mut latencies = latencies_ms.lock().await;
+    latencies.sort_unstable();
+    let median_latency_ms = latencies
+        .get(latencies.len() / 2)
+        .copied()
+        .unwrap_or(0) as f64;
+    drop(latencies);
+
+    let elapsed_secs = last_tick.elapsed().as_secs_f64();
+    let tps = if elapsed_secs > 0.0 {
+        (landed_count.saturating_sub(last_landed)) as f64 / elapsed_secs
+    } else {
+        0.0
+    };
+    *last_tick = Instant::now();
+
+    BENCH_SUBMITTED.set(submitted_count as i64);
+    BENCH_LANDED.set(landed_count as i64);
+    BENCH_LAND_RATE.set(land_rate);
+    BENCH_MEDIAN_CONFIRMATION_MS.set(median_latency_ms);
+    BENCH_TPS.set(tps);
+
+    info!(
+        "benchmark: submitted={submitted_count} landed={landed_count} land_rate={land_rate:.3} median_confirmation_ms={median_latency_ms} tps={tps:.1}"
+    );
+
+    landed_count
+}
+
+async fn fund_payers(
+    rpc_client: &Arc<RpcClient>,
+    identity: &Keypair,
+    payers: &[Keypair],
+) -> anyhow::Result<()> {
+    const LAMPORTS_PER_PAYER: u64 = 10_000_000;
+
+    let blockhash = rpc_client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+        .await?
+        .0;
+
+    let mut signatures: HashMap<Signature, Pubkey> = HashMap::new();
+    for payer in payers {
+        let instruction =
+            system_instruction::transfer(&identity.pubkey(), &payer.pubkey(), LAMPORTS_PER_PAYER);
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&identity.pubkey()),
+            &[identity],
+            blockhash,
+        );
+        let signature = rpc_client.send_transaction(&tx).await?;
+        signatures.insert(signature, payer.pubkey());
+    }
+
+    for (signature, pubkey) in signatures {
+        rpc_client
+            .confirm_transaction_with_commitment(&signature, CommitmentConfig::confirmed())
+            .await
+            .unwrap_or_else(|_| panic!("failed to fund benchmark payer {pubkey}"));
+    }
+
+    Ok(())
+}
+
+fn self_transfer_transaction(payer: &Keypair, blockhash: solana_sdk::hash::Hash) -> Transaction {
+    let instruction = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 1);
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        blockhash,
+    )
+}
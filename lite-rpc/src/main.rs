@@ -8,6 +8,15 @@ use prometheus::{opts, register_int_counter, IntCounter};
 use solana_sdk::signature::Keypair;
 use std::env;
 
+mod benchmark;
+
+use benchmark::{run_benchmark, BenchmarkArgs};
+
+/// `lite-rpc benchmark ...` runs the built-in TPS benchmark instead of starting the bridge.
+/// Kept as a hand-rolled dispatch (rather than a clap subcommand on `Args`) so the existing
+/// `Args` flags keep parsing exactly as before for the default (no subcommand) invocation.
+const BENCHMARK_SUBCOMMAND: &str = "benchmark";
+
 async fn get_identity_keypair(identity_from_cli: &String) -> Keypair {
     if let Ok(identity_env_var) = env::var("IDENTITY") {
         if let Ok(identity_bytes) = serde_json::from_str::<Vec<u8>>(identity_env_var.as_str()) {
@@ -40,6 +49,11 @@ lazy_static::lazy_static! {
 pub async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
 
+    if env::args().nth(1).as_deref() == Some(BENCHMARK_SUBCOMMAND) {
+        let args = BenchmarkArgs::parse_from(env::args().skip(1));
+        return run_benchmark(args).await;
+    }
+
     let Args {
         rpc_addr,
         ws_addr,